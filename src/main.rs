@@ -6,6 +6,7 @@ use std::os::windows::prelude::*;
 
 use std::mem::{size_of, size_of_val, transmute};
 use winapi::shared::minwindef::*;
+use winapi::shared::winerror::ERROR_PARTIAL_COPY;
 use winapi::um::tlhelp32::*;
 use winapi::um::processthreadsapi::*;
 use winapi::um::handleapi::{INVALID_HANDLE_VALUE, CloseHandle};
@@ -15,6 +16,14 @@ use winapi::um::errhandlingapi::*;
 use winapi::um::psapi::*;
 use winapi::um::memoryapi::*;
 use winapi::um::dbghelp::*;
+use winapi::um::winternl::*;
+use winapi::um::synchapi::WaitForSingleObject;
+use winapi::um::sysinfoapi::GetTickCount;
+use winapi::um::securitybaseapi::GetTokenInformation;
+use winapi::um::sddl::ConvertSidToStringSidW;
+
+use std::cell::RefCell;
+use std::path::Path;
 
 use std::iter::Iterator;
 use std::ptr::*;
@@ -146,6 +155,58 @@ fn enum_thread(pid: u32) -> ThreadEntry {
     }
 }
 
+struct Thread {
+    tid: u32,
+    handle: HANDLE,
+}
+
+impl Thread {
+    pub fn tid(&self) -> u32 { self.tid }
+
+    pub fn open(tid: u32) -> Result<Thread, String> {
+        unsafe {
+            let handle = OpenThread(THREAD_SUSPEND_RESUME | THREAD_GET_CONTEXT | THREAD_SET_CONTEXT, 0, tid);
+            if handle == null_mut() { return Err(last_error_str()); }
+            Ok(Thread { tid: tid, handle: handle })
+        }
+    }
+
+    pub fn suspend(&self) -> Result<u32, String> {
+        unsafe {
+            let prev = SuspendThread(self.handle);
+            if prev == DWORD::max_value() { Err(last_error_str()) } else { Ok(prev) }
+        }
+    }
+
+    pub fn resume(&self) -> Result<u32, String> {
+        unsafe {
+            let prev = ResumeThread(self.handle);
+            if prev == DWORD::max_value() { Err(last_error_str()) } else { Ok(prev) }
+        }
+    }
+
+    pub fn get_context(&self) -> Option<CONTEXT> {
+        unsafe {
+            let mut ctx: CONTEXT = std::mem::zeroed();
+            ctx.ContextFlags = CONTEXT_FULL;
+            if GetThreadContext(self.handle, &mut ctx) > 0 { Some(ctx) } else { None }
+        }
+    }
+
+    pub fn set_context(&self, ctx: &mut CONTEXT) -> bool {
+        unsafe {
+            ctx.ContextFlags = CONTEXT_FULL;
+            SetThreadContext(self.handle, ctx) > 0
+        }
+    }
+}
+
+impl Drop for Thread {
+    fn drop(&mut self) {
+        unsafe { CloseHandle(self.handle); }
+    }
+}
+
 // --------------------------------------------
 
 struct ModuleInfo {
@@ -186,6 +247,81 @@ fn enum_module(pid: u32) -> ModuleEntry {
     }
 }
 
+// --------------------------------------------
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum MemoryState {
+    Commit,
+    Reserve,
+    Free,
+    Other(DWORD),
+}
+
+impl MemoryState {
+    fn from_raw(state: DWORD) -> MemoryState {
+        match state {
+            MEM_COMMIT => MemoryState::Commit,
+            MEM_RESERVE => MemoryState::Reserve,
+            MEM_FREE => MemoryState::Free,
+            other => MemoryState::Other(other),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum MemoryType {
+    Image,
+    Mapped,
+    Private,
+    Other(DWORD),
+}
+
+impl MemoryType {
+    fn from_raw(mem_type: DWORD) -> MemoryType {
+        match mem_type {
+            MEM_IMAGE => MemoryType::Image,
+            MEM_MAPPED => MemoryType::Mapped,
+            MEM_PRIVATE => MemoryType::Private,
+            other => MemoryType::Other(other),
+        }
+    }
+}
+
+struct MemoryRegionInfo {
+    base: u64,
+    size: usize,
+    state: MemoryState,
+    protect: DWORD, // raw PAGE_* flags; combinations are too varied to usefully enumerate
+    mem_type: MemoryType,
+}
+
+struct MemoryRegions<'a> {
+    process: &'a Process,
+    address: u64,
+}
+
+impl<'a> Iterator for MemoryRegions<'a> {
+    type Item = MemoryRegionInfo;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            let mut mbi: MEMORY_BASIC_INFORMATION = std::mem::zeroed();
+            let ret = VirtualQueryEx(self.process.handle, self.address as LPVOID,
+                                     &mut mbi, size_of_val(&mbi));
+            if ret == 0 { return None; }
+
+            self.address = mbi.BaseAddress as u64 + mbi.RegionSize as u64;
+            Some(MemoryRegionInfo {
+                base: mbi.BaseAddress as u64,
+                size: mbi.RegionSize as usize,
+                state: MemoryState::from_raw(mbi.State),
+                protect: mbi.Protect,
+                mem_type: MemoryType::from_raw(mbi.Type),
+            })
+        }
+    }
+}
+
 fn get_current_pid() -> DWORD {
     unsafe { GetCurrentProcessId() }
 }
@@ -206,9 +342,94 @@ fn get_last_error() -> DWORD { unsafe { GetLastError() } }
 
 fn last_error_str() -> String { last_error(get_last_error()) }
 
+// winapi only ships the native-width PEB/RTL_USER_PROCESS_PARAMETERS; these mirror the
+// same undocumented layout with 32-bit pointers for reading a WOW64 target's PEB32.
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct UNICODE_STRING32 {
+    Length: u16,
+    MaximumLength: u16,
+    Buffer: u32,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct CURDIR32 {
+    DosPath: UNICODE_STRING32,
+    Handle: u32,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct RTL_USER_PROCESS_PARAMETERS32 {
+    MaximumLength: u32,
+    Length: u32,
+    Flags: u32,
+    DebugFlags: u32,
+    ConsoleHandle: u32,
+    ConsoleFlags: u32,
+    StandardInput: u32,
+    StandardOutput: u32,
+    StandardError: u32,
+    CurrentDirectory: CURDIR32,
+    DllPath: UNICODE_STRING32,
+    ImagePathName: UNICODE_STRING32,
+    CommandLine: UNICODE_STRING32,
+    Environment: u32,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct PEB32 {
+    Reserved1: [u8; 2],
+    BeingDebugged: u8,
+    Reserved2: [u8; 1],
+    Reserved3: [u32; 2],
+    Ldr: u32,
+    ProcessParameters: u32,
+}
+
+// winapi's own RTL_USER_PROCESS_PARAMETERS only exposes ImagePathName and CommandLine
+// (Reserved1/Reserved2 cover the rest), so it can't give us CurrentDirectory or
+// Environment on the native (non-WOW64) path either. Mirror the full native layout here
+// instead, the same way we did for the 32-bit one; CurrentDirectory lands at +0x38 and
+// Environment at +0x80, matching the well-known RTL_USER_PROCESS_PARAMETERS layout.
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct CURDIR64 {
+    DosPath: UNICODE_STRING,
+    Handle: u64,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct RTL_USER_PROCESS_PARAMETERS64 {
+    MaximumLength: u32,
+    Length: u32,
+    Flags: u32,
+    DebugFlags: u32,
+    ConsoleHandle: u64,
+    ConsoleFlags: u32,
+    _padding0: u32,
+    StandardInput: u64,
+    StandardOutput: u64,
+    StandardError: u64,
+    CurrentDirectory: CURDIR64,
+    DllPath: UNICODE_STRING,
+    ImagePathName: UNICODE_STRING,
+    CommandLine: UNICODE_STRING,
+    Environment: u64,
+}
+
+// NtQueryInformationProcess(ProcessCommandLineInformation) is a Windows 8.1+ shortcut
+// that hands back the command line directly, without walking the remote PEB at all.
+const PROCESS_COMMAND_LINE_INFORMATION: PROCESSINFOCLASS = 60;
+
 struct Process {
     pid: u32,
     handle: HANDLE,
+    owner_sid: RefCell<Option<String>>,
+    owner_name: RefCell<Option<String>>,
 }
 
 impl Process {
@@ -233,8 +454,10 @@ impl Process {
             let pid = GetProcessId(handle);
             if pid == 0 { return Err(last_error_str()); }
             SymInitializeW(handle, null_mut(), 1);
+            SymSetOptions(SYMOPT_UNDNAME | SYMOPT_DEFERRED_LOADS);
             return Ok(Process {
                 pid: pid, handle: handle,
+                owner_sid: RefCell::new(None), owner_name: RefCell::new(None),
             });
         }
     }
@@ -274,7 +497,6 @@ impl Process {
             if result == 0 { return None; }
 
             let mut modules = Vec::<ModuleInfo>::new();
-            println!("modules len {}", modules.len());
             for i in 0 .. needed as usize {
                 let hModule = module_handles[i as usize];
                 if hModule == null_mut() { break; }
@@ -288,7 +510,6 @@ impl Process {
                     });
                 }
             }
-            println!("modules len {}", modules.len());
             return Some(modules);
         }
     }
@@ -306,8 +527,68 @@ impl Process {
         }
     }
 
-    // fn read_memory(&self, address: u64, size: usize) -> Option<Vec<u8>> {
-    // }
+    pub fn memory_regions(&self) -> MemoryRegions {
+        MemoryRegions { process: self, address: 0 }
+    }
+
+    pub fn read_memory(&self, address: u64, size: usize) -> Option<Vec<u8>> {
+        unsafe {
+            let mut buf = vec![0u8; size];
+            let mut read = 0 as usize;
+            if ReadProcessMemory(self.handle, address as LPVOID, buf.as_mut_ptr() as LPVOID, size, &mut read) > 0 {
+                buf.truncate(read);
+                return Some(buf);
+            }
+            // A read spanning a guard page fails wholesale with ERROR_PARTIAL_COPY;
+            // binary-search the largest prefix that's actually readable.
+            if get_last_error() == ERROR_PARTIAL_COPY {
+                let mut lo = 0 as usize;
+                let mut hi = size;
+                while lo < hi {
+                    let mid = lo + (hi - lo + 1) / 2;
+                    let mut n = 0 as usize;
+                    if ReadProcessMemory(self.handle, address as LPVOID, buf.as_mut_ptr() as LPVOID, mid, &mut n) > 0 {
+                        lo = mid;
+                    } else {
+                        hi = mid - 1;
+                    }
+                }
+                if lo > 0 {
+                    let mut n = 0 as usize;
+                    ReadProcessMemory(self.handle, address as LPVOID, buf.as_mut_ptr() as LPVOID, lo, &mut n);
+                    buf.truncate(n);
+                    return Some(buf);
+                }
+            }
+            None
+        }
+    }
+
+    pub fn read_u32(&self, address: u64) -> Option<u32> {
+        let buf = self.read_memory(address, size_of::<u32>())?;
+        if buf.len() < size_of::<u32>() { return None; }
+        let mut arr = [0u8; 4];
+        arr.copy_from_slice(&buf[..4]);
+        Some(u32::from_ne_bytes(arr))
+    }
+
+    pub fn read_u64(&self, address: u64) -> Option<u64> {
+        let buf = self.read_memory(address, size_of::<u64>())?;
+        if buf.len() < size_of::<u64>() { return None; }
+        let mut arr = [0u8; 8];
+        arr.copy_from_slice(&buf[..8]);
+        Some(u64::from_ne_bytes(arr))
+    }
+
+    // Pointers are 4 bytes wide in a WOW64 (32-bit) target, not 8, so honor the target's
+    // bitness rather than always reading a native-width value.
+    pub fn read_ptr(&self, address: u64) -> Option<u64> {
+        if self.peb32_address().is_some() {
+            self.read_u32(address).map(|v| v as u64)
+        } else {
+            self.read_u64(address)
+        }
+    }
 
     pub fn write_memory(&self, address: u64, data: &[u8]) -> usize {
         unsafe {
@@ -331,15 +612,317 @@ impl Process {
         }
     }
 
-    // pub fn get_symbol_by_address(&self, address: u64) -> (String, u32) {
-    //     unsafe {
-    //         let mut si: SYMBOL_INFOW = std::mem::zeroed();
-    //         si.SizeOfStruct = size_of_val(&si) as u32;
+    pub fn get_symbol_by_address(&self, address: u64) -> Option<(String, u64)> {
+        unsafe {
+            // SYMBOL_INFOW needs 8-byte alignment (it has DWORD64 fields); a plain
+            // Vec<u8> only guarantees 1-byte alignment, so back it with a Vec<u64>
+            // sized up to the same byte count instead.
+            let words = (size_of::<SYMBOL_INFOW>() + MAX_SYM_NAME * 2 + 7) / 8;
+            let mut buf = vec![0u64; words];
+            let si = buf.as_mut_ptr() as *mut SYMBOL_INFOW;
+            (*si).SizeOfStruct = size_of::<SYMBOL_INFOW>() as u32;
+            (*si).MaxNameLen = MAX_SYM_NAME as u32;
+
+            let mut displacement = 0 as u64;
+            if SymFromAddrW(self.handle, address, &mut displacement, si) > 0 {
+                let name = std::slice::from_raw_parts((*si).Name.as_ptr(), (*si).NameLen as usize);
+                Some((OsString::from_wide(name).into_string().unwrap_or_default(), displacement))
+            } else { None }
+        }
+    }
 
-    //         let name: Vec<u16> = OsStr::new(symbol).encode_wide().collect();
-    //         if SymFromNameW(self.handle, name.as_ptr(), &mut si) > 0 { si.Address as u64 } else { 0 }
-    //     }
-    // }
+    // Resolves an address to "module!name+0x.." the way a debugger would print it.
+    pub fn format_address(&self, address: u64) -> String {
+        let module = self.get_modules().unwrap_or_default().into_iter()
+            .find(|m| address >= m.base && address < m.base + m.size as u64);
+        match self.get_symbol_by_address(address) {
+            Some((name, disp)) => {
+                let module = module.map(|m| m.name.trim_end_matches('\0').to_string()).unwrap_or_else(|| "?".to_string());
+                format!("{}!{}+{:#x}", module, name, disp)
+            }
+            None => format!("{:#x}", address),
+        }
+    }
+
+    fn peb_address(&self) -> Option<u64> {
+        unsafe {
+            let mut pbi: PROCESS_BASIC_INFORMATION = std::mem::zeroed();
+            let mut ret_len = 0 as ULONG;
+            let status = NtQueryInformationProcess(self.handle, ProcessBasicInformation,
+                &mut pbi as *mut _ as PVOID, size_of_val(&pbi) as u32, &mut ret_len);
+            if status == 0 { Some(pbi.PebBaseAddress as u64) } else { None }
+        }
+    }
+
+    // Returns the target's PEB32 address if it's a 32-bit process running under WOW64.
+    fn peb32_address(&self) -> Option<u64> {
+        unsafe {
+            let mut peb32 = 0 as PVOID;
+            let mut ret_len = 0 as ULONG;
+            let status = NtQueryInformationProcess(self.handle, ProcessWow64Information,
+                &mut peb32 as *mut _ as PVOID, size_of_val(&peb32) as u32, &mut ret_len);
+            if status == 0 && peb32 != null_mut() { Some(peb32 as u64) } else { None }
+        }
+    }
+
+    // read_memory hands back a Vec<u8>, which only guarantees 1-byte alignment; casting
+    // its pointer straight to `*const T` and dereferencing would be UB for any T with a
+    // stricter alignment (PEB, RTL_USER_PROCESS_PARAMETERS*, UNICODE_STRING, ...). Copy
+    // the bytes out with an unaligned read into an owned, properly-aligned T instead.
+    fn read_struct<T: Copy>(&self, address: u64) -> Option<T> {
+        let bytes = self.read_memory(address, size_of::<T>())?;
+        if bytes.len() < size_of::<T>() { return None; }
+        Some(unsafe { read_unaligned(bytes.as_ptr() as *const T) })
+    }
+
+    fn read_unicode_string(&self, length: u16, buffer: u64) -> Option<String> {
+        if length == 0 { return Some(String::new()); }
+        let buf = self.read_memory(buffer, length as usize)?;
+        let wide: Vec<u16> = buf.chunks_exact(2).map(|c| u16::from_ne_bytes([c[0], c[1]])).collect();
+        OsString::from_wide(&wide).into_string().ok()
+    }
+
+    // Bounds a read to what's actually committed from `address` to the end of its
+    // region, so callers don't have to guess a size for a block of unknown length.
+    fn committed_region_len(&self, address: u64) -> Option<usize> {
+        unsafe {
+            let mut mbi: MEMORY_BASIC_INFORMATION = std::mem::zeroed();
+            if VirtualQueryEx(self.handle, address as LPVOID, &mut mbi, size_of_val(&mbi)) == 0 {
+                return None;
+            }
+            if mbi.State != MEM_COMMIT { return None; }
+            let region_end = mbi.BaseAddress as u64 + mbi.RegionSize as u64;
+            if address >= region_end { return None; }
+            Some((region_end - address) as usize)
+        }
+    }
+
+    fn read_environment_block(&self, address: u64) -> Option<Vec<String>> {
+        // The block is a double-NUL-terminated run of "KEY=VALUE\0" UTF-16 strings;
+        // read only up to the end of its committed region instead of guessing a size.
+        let raw = self.read_memory(address, self.committed_region_len(address)?)?;
+        let wide: Vec<u16> = raw.chunks_exact(2).map(|c| u16::from_ne_bytes([c[0], c[1]])).collect();
+        let mut vars = Vec::new();
+        let mut start = 0;
+        for i in 0 .. wide.len() {
+            if wide[i] == 0 {
+                if i == start { break; }
+                if let Ok(s) = OsString::from_wide(&wide[start .. i]).into_string() {
+                    vars.push(s);
+                }
+                start = i + 1;
+            }
+        }
+        Some(vars)
+    }
+
+    fn command_line_fast_path(&self) -> Option<String> {
+        unsafe {
+            let mut ret_len = 0 as ULONG;
+            NtQueryInformationProcess(self.handle, PROCESS_COMMAND_LINE_INFORMATION, null_mut(), 0, &mut ret_len);
+            if ret_len == 0 { return None; }
+
+            let mut buf = vec![0u8; ret_len as usize];
+            let status = NtQueryInformationProcess(self.handle, PROCESS_COMMAND_LINE_INFORMATION,
+                buf.as_mut_ptr() as PVOID, ret_len, &mut ret_len);
+            if status != 0 { return None; }
+
+            let us: UNICODE_STRING = read_unaligned(buf.as_ptr() as *const UNICODE_STRING);
+            let wide = std::slice::from_raw_parts(us.Buffer, (us.Length / 2) as usize);
+            OsString::from_wide(wide).into_string().ok()
+        }
+    }
+
+    pub fn command_line(&self) -> Option<String> {
+        if let Some(s) = self.command_line_fast_path() { return Some(s); }
+
+        if let Some(peb32) = self.peb32_address() {
+            let peb: PEB32 = self.read_struct(peb32)?;
+            let params: RTL_USER_PROCESS_PARAMETERS32 = self.read_struct(peb.ProcessParameters as u64)?;
+            return self.read_unicode_string(params.CommandLine.Length, params.CommandLine.Buffer as u64);
+        }
+
+        let peb: PEB = self.read_struct(self.peb_address()?)?;
+        let params: RTL_USER_PROCESS_PARAMETERS64 = self.read_struct(peb.ProcessParameters as u64)?;
+        self.read_unicode_string(params.CommandLine.Length, params.CommandLine.Buffer as u64)
+    }
+
+    pub fn current_directory(&self) -> Option<String> {
+        if let Some(peb32) = self.peb32_address() {
+            let peb: PEB32 = self.read_struct(peb32)?;
+            let params: RTL_USER_PROCESS_PARAMETERS32 = self.read_struct(peb.ProcessParameters as u64)?;
+            return self.read_unicode_string(params.CurrentDirectory.DosPath.Length, params.CurrentDirectory.DosPath.Buffer as u64);
+        }
+
+        let peb: PEB = self.read_struct(self.peb_address()?)?;
+        let params: RTL_USER_PROCESS_PARAMETERS64 = self.read_struct(peb.ProcessParameters as u64)?;
+        self.read_unicode_string(params.CurrentDirectory.DosPath.Length, params.CurrentDirectory.DosPath.Buffer as u64)
+    }
+
+    pub fn environment(&self) -> Option<Vec<String>> {
+        if let Some(peb32) = self.peb32_address() {
+            let peb: PEB32 = self.read_struct(peb32)?;
+            let params: RTL_USER_PROCESS_PARAMETERS32 = self.read_struct(peb.ProcessParameters as u64)?;
+            return self.read_environment_block(params.Environment as u64);
+        }
+
+        let peb: PEB = self.read_struct(self.peb_address()?)?;
+        let params: RTL_USER_PROCESS_PARAMETERS64 = self.read_struct(peb.ProcessParameters as u64)?;
+        self.read_environment_block(params.Environment as u64)
+    }
+
+    // Waits for a just-injected module to show up in the
+    // target's module list; the remote loader completes asynchronously from the thread
+    // we kicked off, so we can't just re-enumerate once.
+    fn wait_for_module(&self, file_name: &str, timeout_ms: u32) -> Result<ModuleInfo, String> {
+        let start = unsafe { GetTickCount() };
+        loop {
+            if let Some(modules) = self.get_modules() {
+                if let Some(m) = modules.into_iter().find(|m| m.name.trim_end_matches('\0').eq_ignore_ascii_case(file_name)) {
+                    return Ok(m);
+                }
+            }
+            if unsafe { GetTickCount() }.wrapping_sub(start) > timeout_ms {
+                return Err("timed out waiting for module".to_string());
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    }
+
+    pub fn inject_dll(&self, path: &Path) -> Result<ModuleInfo, String> {
+        unsafe {
+            let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+            let size = wide.len() * size_of::<u16>();
+
+            let remote_buf = VirtualAllocEx(self.handle, null_mut(), size, MEM_COMMIT | MEM_RESERVE, PAGE_READWRITE);
+            if remote_buf == null_mut() { return Err(last_error_str()); }
+
+            let bytes = std::slice::from_raw_parts(wide.as_ptr() as *const u8, size);
+            if self.write_memory(remote_buf as u64, bytes) != size {
+                VirtualFreeEx(self.handle, remote_buf, 0, MEM_RELEASE);
+                return Err("failed to write dll path into target".to_string());
+            }
+
+            let load_library = self.get_address_by_symbol("kernel32!LoadLibraryW");
+            if load_library == 0 {
+                VirtualFreeEx(self.handle, remote_buf, 0, MEM_RELEASE);
+                return Err("could not resolve kernel32!LoadLibraryW".to_string());
+            }
+
+            let start_routine = transmute::<u64, extern "system" fn(LPVOID) -> DWORD>(load_library);
+            let thread = CreateRemoteThread(self.handle, null_mut(), 0, Some(start_routine), remote_buf, 0, null_mut());
+            if thread == null_mut() {
+                VirtualFreeEx(self.handle, remote_buf, 0, MEM_RELEASE);
+                return Err(last_error_str());
+            }
+            WaitForSingleObject(thread, INFINITE);
+            CloseHandle(thread);
+            VirtualFreeEx(self.handle, remote_buf, 0, MEM_RELEASE);
+
+            let file_name = path.file_name().and_then(|n| n.to_str()).ok_or("invalid dll path")?;
+            self.wait_for_module(file_name, 5000)
+        }
+    }
+
+    pub fn eject_dll(&self, module: &ModuleInfo) -> Result<(), String> {
+        unsafe {
+            let free_library = self.get_address_by_symbol("kernel32!FreeLibrary");
+            if free_library == 0 { return Err("could not resolve kernel32!FreeLibrary".to_string()); }
+
+            let start_routine = transmute::<u64, extern "system" fn(LPVOID) -> DWORD>(free_library);
+            let thread = CreateRemoteThread(self.handle, null_mut(), 0, Some(start_routine), module.base as LPVOID, 0, null_mut());
+            if thread == null_mut() { return Err(last_error_str()); }
+            WaitForSingleObject(thread, INFINITE);
+            CloseHandle(thread);
+            Ok(())
+        }
+    }
+
+    // Returns the raw TOKEN_USER buffer rather than the PSID it contains, since that
+    // PSID just points into this buffer and must not outlive it.
+    fn token_user_buffer(&self) -> Option<Vec<u8>> {
+        unsafe {
+            let mut token = null_mut();
+            if OpenProcessToken(self.handle, TOKEN_QUERY, &mut token) == 0 { return None; }
+
+            let mut needed = 0 as DWORD;
+            GetTokenInformation(token, TokenUser, null_mut(), 0, &mut needed);
+            let mut buf = vec![0u8; needed as usize];
+            let result = GetTokenInformation(token, TokenUser, buf.as_mut_ptr() as LPVOID, needed, &mut needed);
+            CloseHandle(token);
+            if result == 0 { return None; }
+
+            Some(buf)
+        }
+    }
+
+    fn token_user_sid(buf: &[u8]) -> PSID {
+        unsafe { (&*(buf.as_ptr() as *const TOKEN_USER)).User.Sid }
+    }
+
+    pub fn owner_sid(&self) -> Option<String> {
+        if let Some(sid) = self.owner_sid.borrow().as_ref() { return Some(sid.clone()); }
+
+        let buf = self.token_user_buffer()?;
+        let sid = unsafe {
+            let sid = Process::token_user_sid(&buf);
+            let mut sid_str: LPWSTR = null_mut();
+            if ConvertSidToStringSidW(sid, &mut sid_str) == 0 { return None; }
+            let len = (0 .. isize::max_value()).take_while(|&i| *sid_str.offset(i) != 0).count();
+            let s = OsString::from_wide(std::slice::from_raw_parts(sid_str, len)).into_string().ok();
+            LocalFree(sid_str as HLOCAL);
+            s
+        }?;
+
+        *self.owner_sid.borrow_mut() = Some(sid.clone());
+        Some(sid)
+    }
+
+    pub fn owner_name(&self) -> Option<String> {
+        if let Some(name) = self.owner_name.borrow().as_ref() { return Some(name.clone()); }
+
+        let buf = self.token_user_buffer()?;
+        let name = unsafe {
+            let sid = Process::token_user_sid(&buf);
+
+            let mut name_len = 0 as DWORD;
+            let mut domain_len = 0 as DWORD;
+            let mut use_ = 0 as SID_NAME_USE;
+            LookupAccountSidW(null(), sid, null_mut(), &mut name_len, null_mut(), &mut domain_len, &mut use_);
+
+            let mut name = vec![0u16; name_len as usize];
+            let mut domain = vec![0u16; domain_len as usize];
+            if LookupAccountSidW(null(), sid, name.as_mut_ptr(), &mut name_len,
+                                  domain.as_mut_ptr(), &mut domain_len, &mut use_) == 0 {
+                return None;
+            }
+
+            let domain = OsString::from_wide(&domain[.. domain_len as usize]).into_string().ok()?;
+            let name = OsString::from_wide(&name[.. name_len as usize]).into_string().ok()?;
+            format!("{}\\{}", domain, name)
+        };
+
+        *self.owner_name.borrow_mut() = Some(name.clone());
+        Some(name)
+    }
+
+    // Pauses every thread in the process; the standard prelude for a debugger or
+    // crash-reader before walking another process's memory with read_memory.
+    pub fn suspend_all(&self) {
+        for t in enum_thread(self.pid) {
+            if let Ok(thread) = Thread::open(t.tid) {
+                let _ = thread.suspend();
+            }
+        }
+    }
+
+    pub fn resume_all(&self) {
+        for t in enum_thread(self.pid) {
+            if let Ok(thread) = Thread::open(t.tid) {
+                let _ = thread.resume();
+            }
+        }
+    }
 }
 
 // #[test]